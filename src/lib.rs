@@ -1,6 +1,7 @@
 use std::ffi::{c_char, CStr};
 use std::fs::{File, OpenOptions};
-use std::io::{BufWriter, ErrorKind, Write};
+use std::io::{BufWriter, ErrorKind, IoSlice, Seek, SeekFrom, Write};
+use std::os::unix::fs::OpenOptionsExt;
 use std::path::Path;
 use std::ptr::null_mut;
 use std::slice;
@@ -16,6 +17,7 @@ pub enum FileWriterError {
     InvalidPath = 5,
     InvalidData = 6,
     IoError = 7,
+    AlreadyExists = 8,
 }
 
 impl From<std::io::Error> for FileWriterError {
@@ -35,9 +37,28 @@ pub enum FileWriterMode {
     Write = 1,
 }
 
+struct AtomicWriteState {
+    temp_path: std::path::PathBuf,
+    final_path: std::path::PathBuf,
+    committed: bool,
+}
+
 pub struct FileWriter {
     writer: Option<BufWriter<File>>,
     is_valid: bool,
+    atomic: Option<AtomicWriteState>,
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        // If the writer was dropped without a successful `file_writer_close`,
+        // the temp file must not be left behind for a reader to stumble on.
+        if let Some(atomic) = self.atomic.take() {
+            if !atomic.committed {
+                let _ = std::fs::remove_file(&atomic.temp_path);
+            }
+        }
+    }
 }
 
 pub type FileWriterHandle = FileWriter;
@@ -109,6 +130,236 @@ pub unsafe extern "C" fn file_writer_new(
     let file_writer = FileWriter {
         writer: Some(writer),
         is_valid: true,
+        atomic: None,
+    };
+
+    let boxed_writer = Box::new(file_writer);
+    unsafe {
+        *handle = Box::into_raw(boxed_writer);
+    }
+
+    FileWriterError::Success
+}
+
+/// Extra creation options for [`file_writer_new_ext`], exposing the parts of
+/// `OpenOptions` that `file_writer_new` doesn't: Unix permission bits,
+/// exclusive creation, and opting out of automatic parent-directory creation.
+#[repr(C)]
+pub struct FileWriterOpenOptions {
+    /// Unix permission bits applied via `OpenOptionsExt::mode`.
+    pub mode: u32,
+    /// If true, fail with `FileWriterError::AlreadyExists` instead of
+    /// truncating/appending to an existing file.
+    pub create_new: bool,
+    /// If true, skip the automatic `create_dir_all` of the parent directory.
+    pub no_create_parent_dirs: bool,
+}
+
+/// Like `file_writer_new`, but lets the caller set Unix permission bits,
+/// request exclusive creation, and opt out of parent-directory creation.
+///
+/// # Safety
+/// - `path` must be a valid null-terminated C string
+/// - `handle` must be a valid pointer to store the result
+/// - `options` may be null, or must point to a valid `FileWriterOpenOptions`
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_new_ext(
+    path: *const c_char,
+    handle: *mut *mut FileWriterHandle,
+    mode: FileWriterMode,
+    options: *const FileWriterOpenOptions,
+) -> FileWriterError {
+    if path.is_null() {
+        return FileWriterError::InvalidPath;
+    }
+    if handle.is_null() {
+        return FileWriterError::InvalidHandle;
+    }
+    unsafe { *handle = null_mut() };
+
+    let c_str = unsafe { CStr::from_ptr(path) };
+    let path_str = match c_str.to_str() {
+        Ok(s) => s,
+        Err(_) => return FileWriterError::InvalidPath,
+    };
+    let path_obj = Path::new(path_str);
+
+    let options = unsafe { options.as_ref() };
+    let create_new = options.map(|o| o.create_new).unwrap_or(false);
+    let skip_parent_dirs = options.map(|o| o.no_create_parent_dirs).unwrap_or(false);
+
+    if !skip_parent_dirs {
+        if let Some(parent) = path_obj.parent() {
+            if !parent.as_os_str().is_empty() && std::fs::create_dir_all(parent).is_err() {
+                return FileWriterError::FileOpenError;
+            }
+        }
+    }
+
+    let mut open_options = OpenOptions::new();
+    match mode {
+        FileWriterMode::Append => {
+            open_options.append(true);
+        }
+        FileWriterMode::Write => {
+            open_options.write(true).truncate(true);
+        }
+    };
+
+    if create_new {
+        open_options.create_new(true);
+    } else {
+        open_options.create(true);
+    }
+
+    if let Some(o) = options {
+        open_options.mode(o.mode);
+    }
+
+    let file = match open_options.open(path_obj) {
+        Ok(f) => f,
+        Err(e) if create_new && e.kind() == ErrorKind::AlreadyExists => {
+            return FileWriterError::AlreadyExists;
+        }
+        Err(_) => return FileWriterError::FileOpenError,
+    };
+
+    let writer = BufWriter::with_capacity(64 * 1024, file);
+
+    let file_writer = FileWriter {
+        writer: Some(writer),
+        is_valid: true,
+        atomic: None,
+    };
+
+    let boxed_writer = Box::new(file_writer);
+    unsafe {
+        *handle = Box::into_raw(boxed_writer);
+    }
+
+    FileWriterError::Success
+}
+
+/// The Linux/POSIX errno for a rename that crosses filesystem boundaries;
+/// `std::io::ErrorKind` has no stable variant for this yet.
+const EXDEV: i32 = 18;
+
+static ATOMIC_TEMP_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Builds a hidden, process- and call-unique temp file name derived from
+/// `file_name`, e.g. `.data.bin.tmp.8421.3`.
+fn unique_temp_name(file_name: &std::ffi::OsStr) -> std::ffi::OsString {
+    let counter = ATOMIC_TEMP_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let mut name = std::ffi::OsString::new();
+    name.push(".");
+    name.push(file_name);
+    name.push(format!(".tmp.{}.{}", std::process::id(), counter));
+    name
+}
+
+/// Renames `temp_path` onto `final_path`, falling back to a copy+rename
+/// within `final_path`'s directory if the two paths live on different
+/// filesystems (rename(2) returns `EXDEV`).
+fn finalize_atomic_rename(temp_path: &Path, final_path: &Path) -> std::io::Result<()> {
+    match std::fs::rename(temp_path, final_path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(EXDEV) => {
+            let dest_dir = final_path.parent().unwrap_or_else(|| Path::new("."));
+            let fallback_name = unique_temp_name(
+                final_path
+                    .file_name()
+                    .unwrap_or_else(|| std::ffi::OsStr::new("file")),
+            );
+            let fallback_temp = dest_dir.join(fallback_name);
+            std::fs::copy(temp_path, &fallback_temp)?;
+            let _ = std::fs::remove_file(temp_path);
+            let rename_result = std::fs::rename(&fallback_temp, final_path);
+            if rename_result.is_err() {
+                let _ = std::fs::remove_file(&fallback_temp);
+            }
+            rename_result
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Creates a writer in atomic-replace mode: all data is written to a unique
+/// temp file and only moved onto `path` once `file_writer_close` succeeds, so
+/// a reader never observes a half-written file.
+///
+/// # Safety
+/// - `path` must be a valid null-terminated C string
+/// - `handle` must be a valid pointer to store the result
+/// - `temp_dir` may be null, or must point to a valid null-terminated C string
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_new_atomic(
+    path: *const c_char,
+    handle: *mut *mut FileWriterHandle,
+    temp_dir: *const c_char,
+) -> FileWriterError {
+    if path.is_null() {
+        return FileWriterError::InvalidPath;
+    }
+    if handle.is_null() {
+        return FileWriterError::InvalidHandle;
+    }
+    unsafe { *handle = null_mut() };
+
+    let c_str = unsafe { CStr::from_ptr(path) };
+    let path_str = match c_str.to_str() {
+        Ok(s) => s,
+        Err(_) => return FileWriterError::InvalidPath,
+    };
+    let final_path = Path::new(path_str).to_path_buf();
+
+    if let Some(parent) = final_path.parent() {
+        if !parent.as_os_str().is_empty() && std::fs::create_dir_all(parent).is_err() {
+            return FileWriterError::FileOpenError;
+        }
+    }
+
+    let temp_dir_path = if temp_dir.is_null() {
+        final_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| Path::new(".").to_path_buf())
+    } else {
+        let temp_dir_str = match unsafe { CStr::from_ptr(temp_dir) }.to_str() {
+            Ok(s) => s,
+            Err(_) => return FileWriterError::InvalidPath,
+        };
+        if std::fs::create_dir_all(temp_dir_str).is_err() {
+            return FileWriterError::FileOpenError;
+        }
+        Path::new(temp_dir_str).to_path_buf()
+    };
+
+    let file_name = final_path
+        .file_name()
+        .unwrap_or_else(|| std::ffi::OsStr::new("file"));
+    let temp_path = temp_dir_path.join(unique_temp_name(file_name));
+
+    let file_result = OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .open(&temp_path);
+
+    let file = match file_result {
+        Ok(f) => f,
+        Err(_) => return FileWriterError::FileOpenError,
+    };
+
+    let writer = BufWriter::with_capacity(64 * 1024, file);
+
+    let file_writer = FileWriter {
+        writer: Some(writer),
+        is_valid: true,
+        atomic: Some(AtomicWriteState {
+            temp_path,
+            final_path,
+            committed: false,
+        }),
     };
 
     let boxed_writer = Box::new(file_writer);
@@ -186,6 +437,51 @@ pub unsafe extern "C" fn file_writer_write_raw(
     }
 }
 
+/// Performs a single partial `Write::write` instead of `write_all`, reporting
+/// the number of bytes actually accepted via `out_written` even on a short
+/// write. Useful for callers doing their own flow control or writing to
+/// non-regular files (pipes, FIFOs) where short writes are routine.
+///
+/// # Safety
+/// - `handle` must be a valid FileWriterHandle pointer
+/// - `data` must point to valid memory of at least `size` bytes
+/// - `out_written` must be a valid pointer to store the result
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_write(
+    handle: *mut FileWriterHandle,
+    data: *const u8,
+    size: usize,
+    out_written: *mut usize,
+) -> FileWriterError {
+    if out_written.is_null() {
+        return FileWriterError::InvalidData;
+    }
+    unsafe { *out_written = 0 };
+
+    if size == 0 {
+        return FileWriterError::Success;
+    }
+
+    if data.is_null() {
+        return FileWriterError::InvalidData;
+    }
+
+    let writer = match get_writer_mut(handle) {
+        Ok(w) => w,
+        Err(e) => return e,
+    };
+
+    let data_slice = unsafe { slice::from_raw_parts(data, size) };
+
+    match writer.write(data_slice) {
+        Ok(written) => {
+            unsafe { *out_written = written };
+            FileWriterError::Success
+        }
+        Err(_) => FileWriterError::FileWriteError,
+    }
+}
+
 /// # Safety
 /// - `handle` must be a valid FileWriterHandle pointer
 /// - `str_ptr` must be a valid null-terminated C string
@@ -227,6 +523,126 @@ pub unsafe extern "C" fn file_writer_flush(handle: *mut FileWriterHandle) -> Fil
     }
 }
 
+/// Flushes the `BufWriter` and then fsyncs the underlying file, forcing both
+/// its data and metadata to stable storage.
+///
+/// # Safety
+/// - `handle` must be a valid FileWriterHandle pointer
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_sync(handle: *mut FileWriterHandle) -> FileWriterError {
+    let writer = match get_writer_mut(handle) {
+        Ok(w) => w,
+        Err(e) => return e,
+    };
+
+    if writer.flush().is_err() {
+        return FileWriterError::IoError;
+    }
+
+    match writer.get_mut().sync_all() {
+        Ok(_) => FileWriterError::Success,
+        Err(_) => FileWriterError::IoError,
+    }
+}
+
+/// Flushes the `BufWriter` and then fdatasyncs the underlying file, forcing
+/// its data (but not necessarily its metadata) to stable storage.
+///
+/// # Safety
+/// - `handle` must be a valid FileWriterHandle pointer
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_sync_data(handle: *mut FileWriterHandle) -> FileWriterError {
+    let writer = match get_writer_mut(handle) {
+        Ok(w) => w,
+        Err(e) => return e,
+    };
+
+    if writer.flush().is_err() {
+        return FileWriterError::IoError;
+    }
+
+    match writer.get_mut().sync_data() {
+        Ok(_) => FileWriterError::Success,
+        Err(_) => FileWriterError::IoError,
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FileWriterSeekFrom {
+    Start = 0,
+    Current = 1,
+    End = 2,
+}
+
+/// Flushes the `BufWriter` and seeks the underlying file, so buffered bytes
+/// are never stranded at the old cursor position. Subsequent writes resume
+/// from the new offset, except on a handle opened in `FileWriterMode::Append`,
+/// whose writes always go to the end of the file.
+///
+/// # Safety
+/// - `handle` must be a valid FileWriterHandle pointer
+/// - `out_pos` must be a valid pointer to store the resulting absolute position
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_seek(
+    handle: *mut FileWriterHandle,
+    offset: i64,
+    whence: FileWriterSeekFrom,
+    out_pos: *mut u64,
+) -> FileWriterError {
+    if out_pos.is_null() {
+        return FileWriterError::InvalidData;
+    }
+
+    let writer = match get_writer_mut(handle) {
+        Ok(w) => w,
+        Err(e) => return e,
+    };
+
+    if writer.flush().is_err() {
+        return FileWriterError::IoError;
+    }
+
+    let seek_from = match whence {
+        FileWriterSeekFrom::Start => SeekFrom::Start(offset as u64),
+        FileWriterSeekFrom::Current => SeekFrom::Current(offset),
+        FileWriterSeekFrom::End => SeekFrom::End(offset),
+    };
+
+    match writer.get_mut().seek(seek_from) {
+        Ok(pos) => {
+            unsafe { *out_pos = pos };
+            FileWriterError::Success
+        }
+        Err(_) => FileWriterError::IoError,
+    }
+}
+
+/// Flushes the `BufWriter` and then grows or truncates the underlying file to
+/// exactly `len` bytes.
+///
+/// # Safety
+/// - `handle` must be a valid FileWriterHandle pointer
+#[no_mangle]
+pub unsafe extern "C" fn file_writer_set_len(
+    handle: *mut FileWriterHandle,
+    len: u64,
+) -> FileWriterError {
+    let writer = match get_writer_mut(handle) {
+        Ok(w) => w,
+        Err(e) => return e,
+    };
+
+    if writer.flush().is_err() {
+        return FileWriterError::IoError;
+    }
+
+    match writer.get_mut().set_len(len) {
+        Ok(_) => FileWriterError::Success,
+        Err(_) => FileWriterError::IoError,
+    }
+}
+
 #[repr(C)]
 pub struct BufferDescriptor {
     pub data: *const u8,
@@ -257,11 +673,45 @@ pub unsafe extern "C" fn file_writer_write_batch(
 
     let buffer_slice = unsafe { slice::from_raw_parts(buffers, count) };
 
+    let mut total_size: usize = 0;
     for buffer in buffer_slice {
         if buffer.size > 0 {
             if buffer.data.is_null() {
                 return FileWriterError::InvalidData;
             }
+            total_size += buffer.size;
+        }
+    }
+
+    // For batches bigger than the buffer, skip the per-fragment copy into the
+    // BufWriter and hand the whole batch to the kernel with writev(2),
+    // repeating the call on whatever remains after a short write.
+    if total_size > writer.capacity() {
+        if writer.flush().is_err() {
+            return FileWriterError::FileWriteError;
+        }
+
+        let mut io_slices: Vec<IoSlice> = buffer_slice
+            .iter()
+            .filter(|buffer| buffer.size > 0)
+            .map(|buffer| IoSlice::new(unsafe { slice::from_raw_parts(buffer.data, buffer.size) }))
+            .collect();
+
+        let file = writer.get_mut();
+        let mut slices = &mut io_slices[..];
+        while !slices.is_empty() {
+            match file.write_vectored(slices) {
+                Ok(0) => return FileWriterError::FileWriteError,
+                Ok(n) => IoSlice::advance_slices(&mut slices, n),
+                Err(_) => return FileWriterError::FileWriteError,
+            }
+        }
+
+        return FileWriterError::Success;
+    }
+
+    for buffer in buffer_slice {
+        if buffer.size > 0 {
             let data_slice = unsafe { slice::from_raw_parts(buffer.data, buffer.size) };
             if writer.write_all(data_slice).is_err() {
                 return FileWriterError::FileWriteError;
@@ -320,16 +770,29 @@ pub unsafe extern "C" fn file_writer_close(handle: *mut FileWriterHandle) -> Fil
         return FileWriterError::InvalidHandle;
     }
 
-    let boxed_writer = unsafe { Box::from_raw(handle) };
+    let mut boxed_writer = unsafe { Box::from_raw(handle) };
+
+    let writer = match boxed_writer.writer.take() {
+        Some(w) => w,
+        None => return FileWriterError::InvalidHandle,
+    };
+
+    let file = match writer.into_inner() {
+        Ok(f) => f,
+        Err(_) => return FileWriterError::FileCloseError,
+    };
 
-    if let Some(writer) = boxed_writer.writer {
-        match writer.into_inner() {
-            Ok(_file) => FileWriterError::Success,
-            Err(_) => FileWriterError::FileCloseError,
+    if let Some(atomic) = boxed_writer.atomic.as_mut() {
+        if file.sync_all().is_err() {
+            return FileWriterError::FileCloseError;
         }
-    } else {
-        FileWriterError::InvalidHandle
+        if finalize_atomic_rename(&atomic.temp_path, &atomic.final_path).is_err() {
+            return FileWriterError::FileCloseError;
+        }
+        atomic.committed = true;
     }
+
+    FileWriterError::Success
 }
 
 #[cfg(test)]
@@ -374,4 +837,358 @@ mod tests {
         // Directory should still exist after closing the file
         assert!(subdir_path.exists());
     }
+
+    #[test]
+    fn test_write_batch_vectored_large() {
+        // Batch total exceeds the 64 KiB BufWriter capacity, so this should
+        // take the writev fast path instead of the buffered loop.
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("batch.bin");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+
+        let chunk_a = vec![0xAAu8; 40 * 1024];
+        let chunk_b = vec![0xBBu8; 40 * 1024];
+        let buffers = [
+            BufferDescriptor {
+                data: chunk_a.as_ptr(),
+                size: chunk_a.len(),
+            },
+            BufferDescriptor {
+                data: chunk_b.as_ptr(),
+                size: chunk_b.len(),
+            },
+        ];
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            let result = file_writer_new(c_path.as_ptr(), &mut handle, FileWriterMode::Write);
+            assert_eq!(result, FileWriterError::Success);
+
+            let result = file_writer_write_batch(handle, buffers.as_ptr(), buffers.len());
+            assert_eq!(result, FileWriterError::Success);
+
+            assert_eq!(file_writer_close(handle), FileWriterError::Success);
+        }
+
+        let written = std::fs::read(&file_path).expect("Failed to read output file");
+        let mut expected = chunk_a.clone();
+        expected.extend_from_slice(&chunk_b);
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn test_sync_and_sync_data() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("synced.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+        let c_data = CString::new("durable").expect("Failed to create CString");
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            let result = file_writer_new(c_path.as_ptr(), &mut handle, FileWriterMode::Write);
+            assert_eq!(result, FileWriterError::Success);
+
+            assert_eq!(
+                file_writer_write_string(handle, c_data.as_ptr()),
+                FileWriterError::Success
+            );
+
+            // Both should flush the BufWriter and report success on a
+            // perfectly ordinary file.
+            assert_eq!(file_writer_sync(handle), FileWriterError::Success);
+            assert_eq!(file_writer_sync_data(handle), FileWriterError::Success);
+
+            assert_eq!(file_writer_close(handle), FileWriterError::Success);
+        }
+
+        let written = std::fs::read_to_string(&file_path).expect("Failed to read output file");
+        assert_eq!(written, "durable");
+    }
+
+    #[test]
+    fn test_atomic_replace_success() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("atomic.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+
+        // Seed the destination with the "old" content a concurrent reader
+        // should keep seeing until the atomic writer closes.
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                file_writer_new(c_path.as_ptr(), &mut handle, FileWriterMode::Write),
+                FileWriterError::Success
+            );
+            let old_data = CString::new("old").unwrap();
+            file_writer_write_string(handle, old_data.as_ptr());
+            file_writer_close(handle);
+        }
+
+        let mut atomic_handle: *mut FileWriterHandle = std::ptr::null_mut();
+        let new_data = CString::new("new").unwrap();
+        unsafe {
+            assert_eq!(
+                file_writer_new_atomic(c_path.as_ptr(), &mut atomic_handle, std::ptr::null()),
+                FileWriterError::Success
+            );
+            assert_eq!(
+                file_writer_write_string(atomic_handle, new_data.as_ptr()),
+                FileWriterError::Success
+            );
+
+            // A reader opening the path mid-write must still see the old
+            // content; the rename hasn't happened yet.
+            assert_eq!(
+                std::fs::read_to_string(&file_path).expect("Failed to read output file"),
+                "old"
+            );
+
+            assert_eq!(file_writer_close(atomic_handle), FileWriterError::Success);
+        }
+
+        assert_eq!(
+            std::fs::read_to_string(&file_path).expect("Failed to read output file"),
+            "new"
+        );
+    }
+
+    #[test]
+    fn test_atomic_drop_without_close_leaves_no_stray_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("never_closed.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+        let data = CString::new("partial").unwrap();
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                file_writer_new_atomic(c_path.as_ptr(), &mut handle, std::ptr::null()),
+                FileWriterError::Success
+            );
+            assert_eq!(
+                file_writer_write_string(handle, data.as_ptr()),
+                FileWriterError::Success
+            );
+
+            // Simulate the caller dropping the handle instead of calling
+            // file_writer_close; Drop must clean up the temp file itself.
+            drop(Box::from_raw(handle));
+        }
+
+        assert!(!file_path.exists());
+        let remaining: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .expect("Failed to read temp dir")
+            .collect();
+        assert!(
+            remaining.is_empty(),
+            "expected no stray files, found {remaining:?}"
+        );
+    }
+
+    #[test]
+    fn test_atomic_close_failure_leaves_destination_untouched() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let subdir = temp_dir.path().join("subdir");
+        std::fs::create_dir_all(&subdir).expect("Failed to create subdir");
+        let file_path = subdir.join("atomic.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+        let data = CString::new("data").unwrap();
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                file_writer_new_atomic(c_path.as_ptr(), &mut handle, std::ptr::null()),
+                FileWriterError::Success
+            );
+            assert_eq!(
+                file_writer_write_string(handle, data.as_ptr()),
+                FileWriterError::Success
+            );
+
+            // Yank the destination directory (and the temp file inside it)
+            // out from under the writer so the final rename has nowhere to
+            // land.
+            std::fs::remove_dir_all(&subdir).expect("Failed to remove subdir");
+
+            assert_eq!(file_writer_close(handle), FileWriterError::FileCloseError);
+        }
+
+        assert!(!file_path.exists());
+    }
+
+    #[test]
+    fn test_write_partial_reports_bytes_written() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("partial.bin");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+
+        let data = b"hello world";
+        let mut written: usize = usize::MAX;
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                file_writer_new(c_path.as_ptr(), &mut handle, FileWriterMode::Write),
+                FileWriterError::Success
+            );
+
+            let result = file_writer_write(handle, data.as_ptr(), data.len(), &mut written);
+            assert_eq!(result, FileWriterError::Success);
+            assert_eq!(written, data.len());
+
+            // A zero-size write reports zero bytes written without touching the file.
+            let result = file_writer_write(handle, data.as_ptr(), 0, &mut written);
+            assert_eq!(result, FileWriterError::Success);
+            assert_eq!(written, 0);
+
+            assert_eq!(file_writer_close(handle), FileWriterError::Success);
+        }
+
+        let contents = std::fs::read(&file_path).expect("Failed to read output file");
+        assert_eq!(contents, data);
+    }
+
+    #[test]
+    fn test_new_ext_applies_unix_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("secret.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+
+        let options = FileWriterOpenOptions {
+            mode: 0o600,
+            create_new: false,
+            no_create_parent_dirs: false,
+        };
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            let result = file_writer_new_ext(
+                c_path.as_ptr(),
+                &mut handle,
+                FileWriterMode::Write,
+                &options,
+            );
+            assert_eq!(result, FileWriterError::Success);
+            assert_eq!(file_writer_close(handle), FileWriterError::Success);
+        }
+
+        let permissions = std::fs::metadata(&file_path)
+            .expect("Failed to stat output file")
+            .permissions();
+        assert_eq!(permissions.mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_new_ext_create_new_rejects_existing_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("exclusive.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                file_writer_new(c_path.as_ptr(), &mut handle, FileWriterMode::Write),
+                FileWriterError::Success
+            );
+            assert_eq!(file_writer_close(handle), FileWriterError::Success);
+        }
+
+        let options = FileWriterOpenOptions {
+            mode: 0o644,
+            create_new: true,
+            no_create_parent_dirs: false,
+        };
+
+        let mut second_handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            let result = file_writer_new_ext(
+                c_path.as_ptr(),
+                &mut second_handle,
+                FileWriterMode::Write,
+                &options,
+            );
+            assert_eq!(result, FileWriterError::AlreadyExists);
+            assert!(second_handle.is_null());
+        }
+    }
+
+    #[test]
+    fn test_new_ext_can_skip_parent_dir_creation() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("missing_dir/leaf.txt");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+
+        let options = FileWriterOpenOptions {
+            mode: 0o644,
+            create_new: false,
+            no_create_parent_dirs: true,
+        };
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        unsafe {
+            let result = file_writer_new_ext(
+                c_path.as_ptr(),
+                &mut handle,
+                FileWriterMode::Write,
+                &options,
+            );
+            assert_eq!(result, FileWriterError::FileOpenError);
+            assert!(handle.is_null());
+        }
+
+        assert!(!file_path.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn test_seek_and_set_len() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("seek.bin");
+        let c_path =
+            CString::new(file_path.to_string_lossy().as_bytes()).expect("Failed to create CString");
+        let data = CString::new("abcdef").unwrap();
+
+        let mut handle: *mut FileWriterHandle = std::ptr::null_mut();
+        let mut pos: u64 = u64::MAX;
+        unsafe {
+            assert_eq!(
+                file_writer_new(c_path.as_ptr(), &mut handle, FileWriterMode::Write),
+                FileWriterError::Success
+            );
+            assert_eq!(
+                file_writer_write_string(handle, data.as_ptr()),
+                FileWriterError::Success
+            );
+
+            // Rewind to the start and overwrite the first byte.
+            assert_eq!(
+                file_writer_seek(handle, 0, FileWriterSeekFrom::Start, &mut pos),
+                FileWriterError::Success
+            );
+            assert_eq!(pos, 0);
+            let patch = CString::new("X").unwrap();
+            assert_eq!(
+                file_writer_write_string(handle, patch.as_ptr()),
+                FileWriterError::Success
+            );
+
+            // Truncate to 3 bytes.
+            assert_eq!(file_writer_set_len(handle, 3), FileWriterError::Success);
+
+            assert_eq!(file_writer_close(handle), FileWriterError::Success);
+        }
+
+        let contents = std::fs::read(&file_path).expect("Failed to read output file");
+        assert_eq!(contents, b"Xbc");
+    }
 }